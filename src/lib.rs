@@ -1,22 +1,22 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::Error;
 use std::fmt::Formatter;
+use std::rc::Rc;
 
-const PLAYER_1_ID: bool = false;
-const PLAYER_2_ID: bool = true;
+const GAMES_TO_WIN_SET: u8 = 6;
+const POINTS_TO_WIN_TIEBREAK: u8 = 7;
 
 #[derive(PartialEq, Eq, Debug, Hash, Copy, Clone)]
 struct Score(u8);
 
 impl Score {
-    fn new(score: u8) -> Result<Score, String> {
-        score_to_call(score)
-            .map(|_| Score(score))
-            .ok_or(format!("Score {} is not a valid tennis score", score))
+    fn new(score: u8) -> Score {
+        Score(score)
     }
 
-    fn add(&self, point: Point) -> Result<Score, String> {
-        Score::new(self.0 + 1)
+    fn add(&self) -> Score {
+        Score(self.0 + 1)
     }
 }
 
@@ -52,73 +52,195 @@ impl Call for CallNumber {
     }
 }
 
-struct GameScore<'gs> {
-    scores: HashMap<&'gs Player, Score>,
+/// Wraps `Rc<Player>` with identity-based `Eq`/`Hash` so two distinct
+/// players who happen to share a name (and so compare equal by value)
+/// don't collide as the same map entry.
+#[derive(Clone)]
+struct PlayerHandle(Rc<Player>);
+
+impl PlayerHandle {
+    fn new(player: Rc<Player>) -> PlayerHandle {
+        PlayerHandle(player)
+    }
+
+    fn player(&self) -> Rc<Player> {
+        self.0.clone()
+    }
 }
 
-impl<'gs> GameScore<'gs> {
-    fn new() -> GameScore<'gs> {
-        let mut game = GameScore {
-            scores: HashMap::new(),
-        };
+impl PartialEq for PlayerHandle {
+    fn eq(&self, other: &PlayerHandle) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for PlayerHandle {}
+
+impl std::hash::Hash for PlayerHandle {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (Rc::as_ptr(&self.0) as usize).hash(state)
+    }
+}
+
+impl std::fmt::Display for PlayerHandle {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}", self.0)
+    }
+}
 
-        game.scores
-            .insert(Player::player_1(), Score::new(0).unwrap());
-        game.scores
-            .insert(Player::player_2(), Score::new(0).unwrap());
+#[derive(Clone)]
+struct GameScore {
+    player_1: PlayerHandle,
+    player_2: PlayerHandle,
+    scores: HashMap<PlayerHandle, Score>,
+}
+
+impl GameScore {
+    fn new(player_1: Rc<Player>, player_2: Rc<Player>) -> GameScore {
+        let player_1 = PlayerHandle::new(player_1);
+        let player_2 = PlayerHandle::new(player_2);
+
+        let mut scores = HashMap::new();
+        scores.insert(player_1.clone(), Score::new(0));
+        scores.insert(player_2.clone(), Score::new(0));
 
-        game
+        GameScore {
+            player_1,
+            player_2,
+            scores,
+        }
     }
 
-    fn scored(&self, point: Point<'gs>) -> GameScore<'gs> {
-        let mut game = GameScore::new();
+    fn scored(&self, point: Point) -> GameScore {
+        let mut scores = self.scores.clone();
 
-        let old_scores: &HashMap<&Player, Score> = &self.scores;
-        let mut new_scores: HashMap<&Player, Score> =
-            old_scores.into_iter().map(|(k, v)| (*k, *v)).collect();
+        let scoring_player = PlayerHandle::new(point.player);
+        let new_score = self.scores.get(&scoring_player).unwrap().add();
+        scores.insert(scoring_player, new_score);
+
+        GameScore {
+            player_1: self.player_1.clone(),
+            player_2: self.player_2.clone(),
+            scores,
+        }
+    }
 
-        let scoring_player = point.player;
-        let new_score = old_scores.get(scoring_player).unwrap().add(point).unwrap();
-        new_scores.insert(scoring_player, new_score);
+    /// A game is won once a player has at least 4 points and leads by 2 or
+    /// more, covering both a straight 4-love style win and a win taken from
+    /// advantage after deuce.
+    fn winner(&self) -> Option<Rc<Player>> {
+        let player_1_score = self.scores.get(&self.player_1).unwrap().0;
+        let player_2_score = self.scores.get(&self.player_2).unwrap().0;
 
-        game.scores = new_scores;
+        if player_1_score >= 4 && player_1_score >= player_2_score + 2 {
+            return Some(self.player_1.player());
+        }
+        if player_2_score >= 4 && player_2_score >= player_1_score + 2 {
+            return Some(self.player_2.player());
+        }
 
-        game
+        None
     }
 }
 
-impl<'gs> std::fmt::Display for GameScore<'gs> {
+impl std::fmt::Display for GameScore {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        if let Some(winner) = self.winner() {
+            return write!(f, "game {}", winner);
+        }
+
+        let player_1_score = self.scores.get(&self.player_1).unwrap();
+        let player_2_score = self.scores.get(&self.player_2).unwrap();
+
+        if player_1_score.0 >= 3 && player_2_score.0 >= 3 {
+            return match player_1_score.0.cmp(&player_2_score.0) {
+                Ordering::Equal => write!(f, "deuce"),
+                Ordering::Greater => write!(f, "advantage {}", self.player_1),
+                Ordering::Less => write!(f, "advantage {}", self.player_2),
+            };
+        }
+
         write!(
             f,
-            "{}-{}",
-            self.scores.get(Player::player_1()).unwrap(),
-            self.scores.get(Player::player_2()).unwrap()
+            "{} {} - {} {}",
+            self.player_1, player_1_score, player_2_score, self.player_2
         )
     }
 }
 
-pub struct Point<'p> {
-    player: &'p Player,
+#[derive(Debug)]
+pub struct Point {
+    player: Rc<Player>,
+    annotation: Option<Annotation>,
 }
 
-impl<'p> Point<'p> {
-    fn new(player: &'p Player) -> Point<'p> {
-        Point { player }
+impl Point {
+    fn new(player: Rc<Player>) -> Point {
+        Point {
+            player,
+            annotation: None,
+        }
+    }
+
+    fn annotated(player: Rc<Player>, annotation: Annotation) -> Point {
+        Point {
+            player,
+            annotation: Some(annotation),
+        }
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Hash)]
-struct Player {
-    id: bool,
+#[derive(PartialEq, Eq, Debug, Hash, Copy, Clone)]
+enum Annotation {
+    Ace,
+    DoubleFault,
+    Winner,
+}
+
+impl Annotation {
+    fn token(&self) -> &'static str {
+        match self {
+            Annotation::Ace => "ace",
+            Annotation::DoubleFault => "double_fault",
+            Annotation::Winner => "winner",
+        }
+    }
+
+    fn from_token(token: &str) -> Option<Annotation> {
+        match token {
+            "ace" => Some(Annotation::Ace),
+            "double_fault" => Some(Annotation::DoubleFault),
+            "winner" => Some(Annotation::Winner),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Player {
+    name: String,
+    rank: Option<String>,
 }
 
 impl Player {
-    fn player_1<'p>() -> &'p Player {
-        return &Player { id: PLAYER_1_ID };
+    pub fn new(name: &str) -> Player {
+        Player {
+            name: name.to_string(),
+            rank: None,
+        }
     }
-    fn player_2<'p>() -> &'p Player {
-        return &Player { id: PLAYER_2_ID };
+
+    pub fn ranked(name: &str, rank: &str) -> Player {
+        Player {
+            name: name.to_string(),
+            rank: Some(rank.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for Player {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}", self.name)
     }
 }
 
@@ -128,13 +250,12 @@ fn score_to_call(score: u8) -> Option<Box<Call>> {
         1 => Some(Box::new(CallNumber(15))),
         2 => Some(Box::new(CallNumber(30))),
         3 => Some(Box::new(CallNumber(40))),
-        4 => Some(Box::new(CallName("game".to_string()))),
         _ => None,
     };
 }
 
-pub fn score_game(points: Vec<Point>) -> String {
-    let mut game = GameScore::new();
+pub fn score_game(points: Vec<Point>, player_1: Rc<Player>, player_2: Rc<Player>) -> String {
+    let mut game = GameScore::new(player_1, player_2);
 
     for point in points {
         game = game.scored(point)
@@ -143,167 +264,958 @@ pub fn score_game(points: Vec<Point>) -> String {
     return game.to_string();
 }
 
+fn shorthand_tokens(input: &str) -> Vec<String> {
+    if input.contains(',') || input.contains(' ') {
+        input
+            .split(|character: char| character == ',' || character.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .map(str::to_string)
+            .collect()
+    } else {
+        input.chars().map(|character| character.to_string()).collect()
+    }
+}
+
+fn parse_shorthand(
+    input: &str,
+    player_1: Rc<Player>,
+    player_2: Rc<Player>,
+) -> Result<Vec<Point>, String> {
+    let mut points = Vec::new();
+
+    for (index, token) in shorthand_tokens(input).iter().enumerate() {
+        let point = match token.as_str() {
+            "A" => Point::new(player_1.clone()),
+            "B" => Point::new(player_2.clone()),
+            _ => {
+                return Err(format!(
+                    "position {}: invalid token \"{}\"",
+                    index + 1,
+                    token
+                ))
+            }
+        };
+
+        points.push(point);
+    }
+
+    Ok(points)
+}
+
+pub fn score_game_str(input: &str, player_1: &Player, player_2: &Player) -> Result<String, String> {
+    let player_1 = Rc::new(player_1.clone());
+    let player_2 = Rc::new(player_2.clone());
+
+    let points = parse_shorthand(input, player_1.clone(), player_2.clone())?;
+
+    Ok(score_game(points, player_1, player_2))
+}
+
+#[derive(Clone)]
+struct TiebreakScore {
+    player_1: PlayerHandle,
+    player_2: PlayerHandle,
+    points: HashMap<PlayerHandle, u8>,
+}
+
+impl TiebreakScore {
+    fn new(player_1: Rc<Player>, player_2: Rc<Player>) -> TiebreakScore {
+        let player_1 = PlayerHandle::new(player_1);
+        let player_2 = PlayerHandle::new(player_2);
+
+        let mut points = HashMap::new();
+        points.insert(player_1.clone(), 0);
+        points.insert(player_2.clone(), 0);
+
+        TiebreakScore {
+            player_1,
+            player_2,
+            points,
+        }
+    }
+
+    fn scored(&self, point: Point) -> TiebreakScore {
+        let mut points = self.points.clone();
+
+        let scoring_player = PlayerHandle::new(point.player);
+        let new_score = points.get(&scoring_player).unwrap() + 1;
+        points.insert(scoring_player, new_score);
+
+        TiebreakScore {
+            player_1: self.player_1.clone(),
+            player_2: self.player_2.clone(),
+            points,
+        }
+    }
+
+    fn winner(&self) -> Option<Rc<Player>> {
+        let player_1_points = *self.points.get(&self.player_1).unwrap();
+        let player_2_points = *self.points.get(&self.player_2).unwrap();
+
+        if player_1_points >= POINTS_TO_WIN_TIEBREAK && player_1_points >= player_2_points + 2 {
+            return Some(self.player_1.player());
+        }
+        if player_2_points >= POINTS_TO_WIN_TIEBREAK && player_2_points >= player_1_points + 2 {
+            return Some(self.player_2.player());
+        }
+
+        None
+    }
+
+    fn loser_points(&self) -> u8 {
+        let player_1_points = *self.points.get(&self.player_1).unwrap();
+        let player_2_points = *self.points.get(&self.player_2).unwrap();
+
+        player_1_points.min(player_2_points)
+    }
+}
+
+#[derive(Clone)]
+struct Set {
+    player_1: PlayerHandle,
+    player_2: PlayerHandle,
+    games_won: HashMap<PlayerHandle, u8>,
+    game: GameScore,
+    tiebreak: Option<TiebreakScore>,
+    tiebreak_loser_points: Option<u8>,
+}
+
+impl Set {
+    fn new(player_1: Rc<Player>, player_2: Rc<Player>) -> Set {
+        let game = GameScore::new(player_1.clone(), player_2.clone());
+
+        let player_1 = PlayerHandle::new(player_1);
+        let player_2 = PlayerHandle::new(player_2);
+
+        let mut games_won = HashMap::new();
+        games_won.insert(player_1.clone(), 0);
+        games_won.insert(player_2.clone(), 0);
+
+        Set {
+            game,
+            player_1,
+            player_2,
+            games_won,
+            tiebreak: None,
+            tiebreak_loser_points: None,
+        }
+    }
+
+    fn scored(&self, point: Point) -> Set {
+        let player_1_games = *self.games_won.get(&self.player_1).unwrap();
+        let player_2_games = *self.games_won.get(&self.player_2).unwrap();
+
+        if player_1_games == GAMES_TO_WIN_SET && player_2_games == GAMES_TO_WIN_SET {
+            let tiebreak = match &self.tiebreak {
+                Some(tiebreak) => tiebreak.scored(point),
+                None => TiebreakScore::new(self.player_1.player(), self.player_2.player())
+                    .scored(point),
+            };
+
+            return match tiebreak.winner() {
+                Some(winner) => {
+                    let mut games_won = self.games_won.clone();
+                    *games_won.get_mut(&PlayerHandle::new(winner)).unwrap() += 1;
+
+                    Set {
+                        player_1: self.player_1.clone(),
+                        player_2: self.player_2.clone(),
+                        game: GameScore::new(self.player_1.player(), self.player_2.player()),
+                        tiebreak: None,
+                        tiebreak_loser_points: Some(tiebreak.loser_points()),
+                        games_won,
+                    }
+                }
+                None => Set {
+                    player_1: self.player_1.clone(),
+                    player_2: self.player_2.clone(),
+                    games_won: self.games_won.clone(),
+                    game: GameScore::new(self.player_1.player(), self.player_2.player()),
+                    tiebreak: Some(tiebreak),
+                    tiebreak_loser_points: None,
+                },
+            };
+        }
+
+        let game = self.game.scored(point);
+
+        match game.winner() {
+            Some(winner) => {
+                let mut games_won = self.games_won.clone();
+                *games_won.get_mut(&PlayerHandle::new(winner)).unwrap() += 1;
+
+                Set {
+                    player_1: self.player_1.clone(),
+                    player_2: self.player_2.clone(),
+                    game: GameScore::new(self.player_1.player(), self.player_2.player()),
+                    tiebreak: None,
+                    tiebreak_loser_points: None,
+                    games_won,
+                }
+            }
+            None => Set {
+                player_1: self.player_1.clone(),
+                player_2: self.player_2.clone(),
+                games_won: self.games_won.clone(),
+                game,
+                tiebreak: None,
+                tiebreak_loser_points: None,
+            },
+        }
+    }
+
+    fn winner(&self) -> Option<Rc<Player>> {
+        let player_1_games = *self.games_won.get(&self.player_1).unwrap();
+        let player_2_games = *self.games_won.get(&self.player_2).unwrap();
+
+        if (player_1_games >= GAMES_TO_WIN_SET && player_1_games >= player_2_games + 2)
+            || (player_1_games == GAMES_TO_WIN_SET + 1 && player_2_games == GAMES_TO_WIN_SET)
+        {
+            return Some(self.player_1.player());
+        }
+        if (player_2_games >= GAMES_TO_WIN_SET && player_2_games >= player_1_games + 2)
+            || (player_2_games == GAMES_TO_WIN_SET + 1 && player_1_games == GAMES_TO_WIN_SET)
+        {
+            return Some(self.player_2.player());
+        }
+
+        None
+    }
+}
+
+impl std::fmt::Display for Set {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(
+            f,
+            "{}-{}",
+            self.games_won.get(&self.player_1).unwrap(),
+            self.games_won.get(&self.player_2).unwrap()
+        )?;
+
+        if let Some(loser_points) = self.tiebreak_loser_points {
+            write!(f, "({})", loser_points)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Match {
+    best_of: u8,
+    player_1: PlayerHandle,
+    player_2: PlayerHandle,
+    sets_won: HashMap<PlayerHandle, u8>,
+    completed_sets: Vec<Set>,
+    current_set: Set,
+}
+
+impl Match {
+    fn new(player_1: Rc<Player>, player_2: Rc<Player>, best_of: u8) -> Match {
+        let current_set = Set::new(player_1.clone(), player_2.clone());
+
+        let player_1 = PlayerHandle::new(player_1);
+        let player_2 = PlayerHandle::new(player_2);
+
+        let mut sets_won = HashMap::new();
+        sets_won.insert(player_1.clone(), 0);
+        sets_won.insert(player_2.clone(), 0);
+
+        Match {
+            current_set,
+            player_1,
+            player_2,
+            best_of,
+            sets_won,
+            completed_sets: Vec::new(),
+        }
+    }
+
+    fn scored(&self, point: Point) -> Match {
+        let set = self.current_set.scored(point);
+
+        match set.winner() {
+            Some(winner) => {
+                let mut sets_won = self.sets_won.clone();
+                *sets_won.get_mut(&PlayerHandle::new(winner)).unwrap() += 1;
+
+                let mut completed_sets = self.completed_sets.clone();
+                completed_sets.push(set);
+
+                Match {
+                    player_1: self.player_1.clone(),
+                    player_2: self.player_2.clone(),
+                    best_of: self.best_of,
+                    sets_won,
+                    completed_sets,
+                    current_set: Set::new(self.player_1.player(), self.player_2.player()),
+                }
+            }
+            None => Match {
+                player_1: self.player_1.clone(),
+                player_2: self.player_2.clone(),
+                best_of: self.best_of,
+                sets_won: self.sets_won.clone(),
+                completed_sets: self.completed_sets.clone(),
+                current_set: set,
+            },
+        }
+    }
+
+    fn sets_needed_to_win(&self) -> u8 {
+        self.best_of / 2 + 1
+    }
+
+    fn winner(&self) -> Option<Rc<Player>> {
+        let needed = self.sets_needed_to_win();
+        let player_1_sets = *self.sets_won.get(&self.player_1).unwrap();
+        let player_2_sets = *self.sets_won.get(&self.player_2).unwrap();
+
+        if player_1_sets >= needed {
+            return Some(self.player_1.player());
+        }
+        if player_2_sets >= needed {
+            return Some(self.player_2.player());
+        }
+
+        None
+    }
+}
+
+impl std::fmt::Display for Match {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        let rendered: Vec<String> = self.completed_sets.iter().map(Set::to_string).collect();
+
+        write!(f, "{}", rendered.join(", "))
+    }
+}
+
+pub fn score_match(
+    points: Vec<Point>,
+    player_1: Rc<Player>,
+    player_2: Rc<Player>,
+    best_of: u8,
+) -> String {
+    let mut game_match = Match::new(player_1, player_2, best_of);
+
+    for point in points {
+        game_match = game_match.scored(point);
+
+        if game_match.winner().is_some() {
+            break;
+        }
+    }
+
+    return game_match.to_string();
+}
+
+#[derive(PartialEq, Eq, Debug)]
+struct RecordError {
+    line: usize,
+    token: String,
+}
+
+impl std::fmt::Display for RecordError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(
+            f,
+            "line {}: unknown annotation \"{}\"",
+            self.line, self.token
+        )
+    }
+}
+
+pub fn write_record(points: &[Point], best_of: Option<u8>) -> String {
+    let mut lines: Vec<String> = Vec::new();
+
+    if let Some(best_of) = best_of {
+        lines.push(format!("best_of={}", best_of));
+    }
+
+    lines.extend(points.iter().map(|point| match point.annotation {
+        Some(annotation) => format!("{} {}", point.player.name, annotation.token()),
+        None => point.player.name.clone(),
+    }));
+
+    lines.join("\n")
+}
+
+pub fn parse_record(record: &str) -> Result<Vec<Point>, String> {
+    let mut points = Vec::new();
+    let mut players: HashMap<String, Rc<Player>> = HashMap::new();
+
+    for (index, raw_line) in record.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.contains('=') {
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace();
+        let name = tokens.next().unwrap();
+        let player = players
+            .entry(name.to_string())
+            .or_insert_with(|| Rc::new(Player::new(name)))
+            .clone();
+
+        let point = match tokens.next() {
+            None => Point::new(player),
+            Some(token) => match Annotation::from_token(token) {
+                Some(annotation) => Point::annotated(player, annotation),
+                None => {
+                    return Err(RecordError {
+                        line: index + 1,
+                        token: token.to_string(),
+                    }
+                    .to_string())
+                }
+            },
+        };
+
+        points.push(point);
+    }
+
+    Ok(points)
+}
+
+fn record_property<'r>(record: &'r str, key: &str) -> Option<&'r str> {
+    let prefix = format!("{}=", key);
+
+    record
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with(&prefix))
+        .map(|line| &line[prefix.len()..])
+}
+
+pub fn replay(record: &str) -> String {
+    let points = match parse_record(record) {
+        Ok(points) => points,
+        Err(error) => return error,
+    };
+
+    let mut players_in_order: Vec<Rc<Player>> = Vec::new();
+    for point in &points {
+        if !players_in_order.contains(&point.player) {
+            players_in_order.push(point.player.clone());
+        }
+    }
+
+    let player_1 = players_in_order
+        .first()
+        .cloned()
+        .unwrap_or_else(|| Rc::new(Player::new("player1")));
+    let player_2 = players_in_order
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| Rc::new(Player::new("player2")));
+
+    match record_property(record, "best_of").and_then(|value| value.parse::<u8>().ok()) {
+        Some(best_of) => score_match(points, player_1, player_2, best_of),
+        None => score_game(points, player_1, player_2),
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::rc::Rc;
+    use Annotation;
     use GameScore;
+    use Match;
     use Player;
-    use PLAYER_1_ID;
-    use PLAYER_2_ID;
     use Point;
     use Score;
+    use Set;
+    use parse_record;
+    use replay;
     use score_game;
+    use score_game_str;
+    use score_match;
+    use write_record;
 
     #[test]
     fn score_should_be_love_for_0() {
-        assert_eq!(format!("{}", Score::new(0).unwrap()), "love")
+        assert_eq!(format!("{}", Score::new(0)), "love")
     }
 
     #[test]
     fn score_should_be_15_for_1() {
-        assert_eq!(format!("{}", Score::new(1).unwrap()), "15")
+        assert_eq!(format!("{}", Score::new(1)), "15")
     }
 
     #[test]
     fn score_should_be_30_for_2() {
-        assert_eq!(format!("{}", Score::new(2).unwrap()), "30")
+        assert_eq!(format!("{}", Score::new(2)), "30")
     }
 
     #[test]
     fn score_should_be_40_for_3() {
-        assert_eq!(format!("{}", Score::new(3).unwrap()), "40")
+        assert_eq!(format!("{}", Score::new(3)), "40")
+    }
+
+    #[test]
+    fn score_should_of_0_plus_one_point_is_15() {
+        assert_eq!(format!("{}", Score::new(0).add()), "15")
     }
 
     #[test]
-    fn score_should_be_game_for_4() {
-        assert_eq!(format!("{}", Score::new(4).unwrap()), "game")
+    fn score_should_of_1_plus_one_point_is_30() {
+        assert_eq!(format!("{}", Score::new(1).add()), "30")
     }
 
     #[test]
-    fn score_should_never_be_more_than_4() {
-        assert_eq!(Score::new(5).is_err(), true)
+    fn score_should_of_2_plus_one_point_is_40() {
+        assert_eq!(format!("{}", Score::new(2).add()), "40")
     }
 
     #[test]
-    fn score_should_of_0_plus_one_point_is_15() {
+    fn player_should_have_a_name() {
+        assert_eq!(Player::new("Federer").to_string(), "Federer")
+    }
+
+    #[test]
+    fn player_should_be_unranked_by_default() {
+        assert_eq!(Player::new("Federer").rank, None)
+    }
+
+    #[test]
+    fn player_should_have_an_optional_rank() {
         assert_eq!(
-            format!(
-                "{}",
-                Score::new(0)
-                    .unwrap()
-                    .add(Point {
-                        player: &Player::player_1(),
-                    })
-                    .unwrap()
+            Player::ranked("Federer", "1").rank,
+            Some("1".to_string())
+        )
+    }
+
+    #[test]
+    fn game_score_should_be_love_love_for_no_scores() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+
+        assert_eq!(
+            format!("{}", GameScore::new(player_1, player_2)),
+            "player1 love - love player2"
+        )
+    }
+
+    #[test]
+    fn game_score_should_allow_adding_one_point_to_player_1() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let game_score =
+            GameScore::new(player_1.clone(), player_2).scored(Point::new(player_1));
+
+        assert_eq!(format!("{}", game_score), "player1 15 - love player2")
+    }
+
+    #[test]
+    fn game_score_should_allow_adding_one_point_to_player_2() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let game_score =
+            GameScore::new(player_1, player_2.clone()).scored(Point::new(player_2));
+
+        assert_eq!(format!("{}", game_score), "player1 love - 15 player2")
+    }
+
+    #[test]
+    fn game_score_should_allow_adding_two_points_to_player_2() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let game_score = GameScore::new(player_1, player_2.clone())
+            .scored(Point::new(player_2.clone()))
+            .scored(Point::new(player_2));
+
+        assert_eq!(game_score.to_string(), "player1 love - 30 player2")
+    }
+
+    #[test]
+    fn game_score_should_be_deuce_at_40_40() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let mut game = GameScore::new(player_1.clone(), player_2.clone());
+        for _ in 0..3 {
+            game = game.scored(Point::new(player_1.clone()));
+            game = game.scored(Point::new(player_2.clone()));
+        }
+
+        assert_eq!(game.to_string(), "deuce")
+    }
+
+    #[test]
+    fn game_score_should_be_advantage_player_1_after_deuce() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let mut game = GameScore::new(player_1.clone(), player_2.clone());
+        for _ in 0..3 {
+            game = game.scored(Point::new(player_1.clone()));
+            game = game.scored(Point::new(player_2.clone()));
+        }
+        game = game.scored(Point::new(player_1));
+
+        assert_eq!(game.to_string(), "advantage player1")
+    }
+
+    #[test]
+    fn game_score_should_return_to_deuce_when_trailing_player_scores_from_advantage() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let mut game = GameScore::new(player_1.clone(), player_2.clone());
+        for _ in 0..3 {
+            game = game.scored(Point::new(player_1.clone()));
+            game = game.scored(Point::new(player_2.clone()));
+        }
+        game = game
+            .scored(Point::new(player_1))
+            .scored(Point::new(player_2));
+
+        assert_eq!(game.to_string(), "deuce")
+    }
+
+    #[test]
+    fn game_score_should_be_won_by_player_holding_advantage_when_they_score_again() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let mut game = GameScore::new(player_1.clone(), player_2.clone());
+        for _ in 0..3 {
+            game = game.scored(Point::new(player_1.clone()));
+            game = game.scored(Point::new(player_2.clone()));
+        }
+        game = game
+            .scored(Point::new(player_1.clone()))
+            .scored(Point::new(player_1));
+
+        assert_eq!(game.to_string(), "game player1")
+    }
+
+    #[test]
+    fn game_score_should_be_won_at_four_points_when_opponent_has_two_or_fewer() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let mut game = GameScore::new(player_1, player_2.clone());
+        for _ in 0..4 {
+            game = game.scored(Point::new(player_2.clone()));
+        }
+
+        assert_eq!(game.to_string(), "game player2")
+    }
+
+    #[test]
+    fn score_game_should_be_love_love_for_no_scores() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+
+        assert_eq!(
+            score_game(vec![], player_1, player_2),
+            "player1 love - love player2"
+        )
+    }
+
+    #[test]
+    fn score_game_should_be_love_15_for_a_single_player_2_point() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+
+        assert_eq!(
+            score_game(
+                vec![Point::new(player_2.clone())],
+                player_1,
+                player_2,
             ),
-            "15"
+            "player1 love - 15 player2"
         )
     }
 
     #[test]
-    fn score_should_of_1_plus_one_point_is_30() {
+    fn score_game_should_report_a_game_win() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+
         assert_eq!(
-            format!(
-                "{}",
-                Score::new(1)
-                    .unwrap()
-                    .add(Point {
-                        player: &Player::player_1(),
-                    })
-                    .unwrap()
+            score_game(
+                vec![
+                    Point::new(player_1.clone()),
+                    Point::new(player_1.clone()),
+                    Point::new(player_1.clone()),
+                    Point::new(player_1.clone()),
+                ],
+                player_1,
+                player_2,
             ),
-            "30"
+            "game player1"
         )
     }
 
     #[test]
-    fn score_should_of_2_plus_one_point_is_40() {
+    fn score_game_should_attribute_the_score_to_named_players() {
+        let federer = Rc::new(Player::new("Federer"));
+        let nadal = Rc::new(Player::new("Nadal"));
+
         assert_eq!(
-            format!(
-                "{}",
-                Score::new(2)
-                    .unwrap()
-                    .add(Point {
-                        player: Player::player_1(),
-                    })
-                    .unwrap()
+            score_game(
+                vec![Point::new(federer.clone())],
+                federer,
+                nadal,
             ),
-            "40"
+            "Federer 15 - love Nadal"
         )
     }
 
     #[test]
-    fn score_should_of_3_plus_one_point_is_game() {
+    fn score_game_should_not_conflate_two_distinct_players_sharing_a_name() {
+        let player_1 = Rc::new(Player::new("Same"));
+        let player_2 = Rc::new(Player::new("Same"));
+
         assert_eq!(
-            format!(
-                "{}",
-                Score::new(3)
-                    .unwrap()
-                    .add(Point {
-                        player: Player::player_1(),
-                    })
-                    .unwrap()
+            score_game(
+                vec![Point::new(player_1.clone())],
+                player_1,
+                player_2,
             ),
-            "game"
+            "Same 15 - love Same"
         )
     }
 
     #[test]
-    fn score_should_of_4_plus_one_point_errors() {
-        assert!(
-            Score::new(4)
-                .unwrap()
-                .add(Point {
-                    player: &Player::player_1(),
-                })
-                .is_err()
+    fn set_should_be_won_six_games_to_love() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let mut set = Set::new(player_1.clone(), player_2);
+        for _ in 0..6 {
+            for _ in 0..4 {
+                set = set.scored(Point::new(player_1.clone()));
+            }
+        }
+
+        assert_eq!(set.to_string(), "6-0")
+    }
+
+    #[test]
+    fn set_should_be_won_six_games_to_four() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let mut set = Set::new(player_1.clone(), player_2.clone());
+        for _ in 0..4 {
+            for _ in 0..4 {
+                set = set.scored(Point::new(player_2.clone()));
+            }
+        }
+        for _ in 0..6 {
+            for _ in 0..4 {
+                set = set.scored(Point::new(player_1.clone()));
+            }
+        }
+
+        assert_eq!(set.to_string(), "6-4")
+    }
+
+    #[test]
+    fn set_should_be_decided_by_a_tiebreak_at_six_games_all() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let mut set = Set::new(player_1.clone(), player_2.clone());
+        for _ in 0..6 {
+            for _ in 0..4 {
+                set = set.scored(Point::new(player_1.clone()));
+            }
+            for _ in 0..4 {
+                set = set.scored(Point::new(player_2.clone()));
+            }
+        }
+        for _ in 0..5 {
+            set = set.scored(Point::new(player_1.clone()));
+        }
+        for _ in 0..7 {
+            set = set.scored(Point::new(player_2.clone()));
+        }
+
+        assert_eq!(set.to_string(), "6-7(5)")
+    }
+
+    #[test]
+    fn score_match_should_report_a_straight_sets_win() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let mut points = Vec::new();
+        for _ in 0..2 {
+            for _ in 0..6 {
+                for _ in 0..4 {
+                    points.push(Point::new(player_1.clone()));
+                }
+            }
+        }
+
+        assert_eq!(score_match(points, player_1, player_2, 3), "6-0, 6-0")
+    }
+
+    #[test]
+    fn score_match_should_declare_a_winner_once_a_majority_of_sets_is_taken() {
+        let player_1 = Rc::new(Player::new("player1"));
+        let player_2 = Rc::new(Player::new("player2"));
+        let mut game_match = Match::new(player_1.clone(), player_2, 3);
+
+        for _ in 0..2 {
+            for _ in 0..6 {
+                for _ in 0..4 {
+                    game_match = game_match.scored(Point::new(player_1.clone()));
+                }
+            }
+        }
+
+        assert_eq!(game_match.winner(), Some(player_1))
+    }
+
+    #[test]
+    fn write_record_should_render_one_line_per_point() {
+        let player_1 = Rc::new(Player::new("Federer"));
+        let player_2 = Rc::new(Player::new("Nadal"));
+
+        let points = vec![
+            Point::annotated(player_1, Annotation::Ace),
+            Point::new(player_2),
+        ];
+
+        assert_eq!(write_record(&points, None), "Federer ace\nNadal")
+    }
+
+    #[test]
+    fn write_record_should_prefix_a_best_of_header_when_given() {
+        let player_1 = Rc::new(Player::new("Federer"));
+
+        let points = vec![Point::new(player_1)];
+
+        assert_eq!(
+            write_record(&points, Some(3)),
+            "best_of=3\nFederer"
         )
     }
 
     #[test]
-    fn player_should_have_a_player_1() {
-        assert_eq!(Player::player_1(), &Player { id: PLAYER_1_ID })
+    fn parse_record_should_reconstruct_the_point_stream() {
+        let points = parse_record("Federer ace\nNadal\nFederer double_fault").unwrap();
+
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].player.name, "Federer");
+        assert_eq!(points[0].annotation, Some(Annotation::Ace));
+        assert_eq!(points[1].player.name, "Nadal");
+        assert_eq!(points[1].annotation, None);
+        assert_eq!(points[2].annotation, Some(Annotation::DoubleFault));
     }
 
     #[test]
-    fn player_should_have_a_player_2() {
-        assert_eq!(Player::player_2(), &Player { id: PLAYER_2_ID })
+    fn parse_record_should_reject_an_unknown_annotation() {
+        let error = parse_record("Federer ace\nNadal smash").unwrap_err();
+
+        assert_eq!(error, "line 2: unknown annotation \"smash\"")
     }
 
     #[test]
-    fn game_score_should_be_love_love_for_no_scores() {
-        assert_eq!(format!("{}", GameScore::new()), "love-love")
+    fn replay_should_reproduce_a_game_scoreboard() {
+        let record = "Federer\nFederer\nFederer\nFederer";
+
+        assert_eq!(replay(record), "game Federer")
     }
 
     #[test]
-    fn game_score_should_allow_adding_one_point_to_player_1() {
-        let game_score = GameScore::new().scored(Point::new(Player::player_1()));
+    fn replay_should_surface_a_parse_error() {
+        assert_eq!(
+            replay("Federer smash"),
+            "line 1: unknown annotation \"smash\""
+        )
+    }
 
-        assert_eq!(format!("{}", game_score), "15-love")
+    #[test]
+    fn replay_should_reproduce_a_match_scoreboard_when_best_of_is_given() {
+        let player_1 = Rc::new(Player::new("Federer"));
+        let mut points = Vec::new();
+        for _ in 0..2 {
+            for _ in 0..6 {
+                for _ in 0..4 {
+                    points.push(Point::new(player_1.clone()));
+                }
+            }
+        }
+
+        let record = write_record(&points, Some(3));
+
+        assert_eq!(replay(&record), "6-0, 6-0")
     }
 
     #[test]
-    fn game_score_should_allow_adding_one_point_to_player_2() {
-        let game_score = GameScore::new().scored(Point::new(Player::player_2()));
+    fn write_record_and_parse_record_should_round_trip() {
+        let player_1 = Rc::new(Player::new("Federer"));
+        let player_2 = Rc::new(Player::new("Nadal"));
+
+        let points = vec![
+            Point::new(player_1.clone()),
+            Point::annotated(player_2, Annotation::Winner),
+            Point::new(player_1),
+        ];
+
+        let record = write_record(&points, None);
+        let parsed = parse_record(&record).unwrap();
 
-        assert_eq!(format!("{}", game_score), "love-15")
+        assert_eq!(write_record(&parsed, None), record)
     }
 
     #[test]
-    fn game_score_should_allow_adding_two_points_to_player_2() {
-        let game_score = GameScore::new()
-            .scored(Point::new(Player::player_2()))
-            .scored(Point::new(Player::player_2()));
+    fn write_record_and_replay_should_round_trip_a_finished_match() {
+        let player_1 = Rc::new(Player::new("Federer"));
+        let player_2 = Rc::new(Player::new("Nadal"));
+        let mut points = Vec::new();
+        for _ in 0..2 {
+            for _ in 0..6 {
+                for _ in 0..4 {
+                    points.push(Point::new(player_1.clone()));
+                }
+            }
+        }
+
+        let record = write_record(&points, Some(3));
+
+        assert_eq!(
+            score_match(points, player_1, player_2, 3),
+            replay(&record)
+        )
+    }
 
-        assert_eq!(game_score.to_string(), "love-30")
+    #[test]
+    fn score_game_str_should_score_a_compact_shorthand_sequence() {
+        let player_1 = Player::new("player1");
+        let player_2 = Player::new("player2");
+
+        assert_eq!(
+            score_game_str("ABBA", &player_1, &player_2).unwrap(),
+            "player1 30 - 30 player2"
+        )
     }
 
     #[test]
-    fn score_game_should_be_love_love_for_no_scores() {
-        assert_eq!(score_game(vec![]), "love-love")
+    fn score_game_str_should_score_a_comma_separated_sequence() {
+        let player_1 = Player::new("player1");
+        let player_2 = Player::new("player2");
+
+        assert_eq!(
+            score_game_str("A, A, A, A", &player_1, &player_2).unwrap(),
+            "game player1"
+        )
     }
 
     #[test]
-    fn score_game_should_be_love_15_for_a_single_player_2_point() {
-        assert_eq!(score_game(vec![Point::new(Player::player_2())]), "love-15")
+    fn score_game_str_should_score_a_space_separated_sequence() {
+        let player_1 = Player::new("player1");
+        let player_2 = Player::new("player2");
+
+        assert_eq!(
+            score_game_str("A A A A", &player_1, &player_2).unwrap(),
+            "game player1"
+        )
+    }
+
+    #[test]
+    fn score_game_str_should_reject_an_invalid_token() {
+        let player_1 = Player::new("player1");
+        let player_2 = Player::new("player2");
+
+        let error = score_game_str("ABC", &player_1, &player_2).unwrap_err();
+
+        assert_eq!(error, "position 3: invalid token \"C\"")
     }
 }